@@ -1,5 +1,7 @@
 use crate::cli::Action;
 use crate::error::{Error, Result};
+use crate::utils::format_bytes;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -54,11 +56,127 @@ pub fn execute_action(
             }
             println!("Moved: {} image(s)", orphaned_images.len());
         }
+        Action::Report => {
+            unreachable!("Action::Report is handled in main before execute_action is called")
+        }
+        Action::Dedupe => {
+            let mut deduped = 0usize;
+            let mut reclaimed = 0u64;
+
+            for group in group_duplicates(orphaned_images)? {
+                let (kept, duplicates) = group.split_first().expect("duplicate groups are never empty");
+                for duplicate in duplicates {
+                    // Already a hard link to `kept` (e.g. from a previous
+                    // run) — nothing to do, and relinking it would free no
+                    // space while still counting its size as reclaimed.
+                    if same_inode(kept, duplicate) {
+                        continue;
+                    }
+
+                    let size = fs::metadata(duplicate).map(|m| m.len()).unwrap_or(0);
+                    match hard_link_duplicate(kept, duplicate) {
+                        Ok(()) => {
+                            deduped += 1;
+                            reclaimed += size;
+                        }
+                        Err(source) => {
+                            eprintln!(
+                                "warning: could not hard-link duplicate {} (left untouched): {}",
+                                duplicate.display(),
+                                source
+                            );
+                        }
+                    }
+                }
+            }
+
+            // An estimate: space is only actually freed once the last
+            // remaining link to a duplicate's data is dropped, which may
+            // not happen if something outside this scan still links to it.
+            println!(
+                "Deduplicated: {} image(s), ~{} reclaimed",
+                deduped,
+                format_bytes(reclaimed)
+            );
+        }
     }
 
     Ok(())
 }
 
+/// Group images that are byte-identical, first by size and then by content
+/// hash. Groups of size 1 (no duplicate) are dropped.
+fn group_duplicates(images: &[PathBuf]) -> Result<Vec<Vec<PathBuf>>> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for img in images {
+        let size = fs::metadata(img)
+            .map_err(|source| Error::ReadFile {
+                path: img.clone(),
+                source,
+            })?
+            .len();
+        by_size.entry(size).or_default().push(img.clone());
+    }
+
+    let mut groups = Vec::new();
+    for candidates in by_size.into_values() {
+        if candidates.len() < 2 {
+            continue;
+        }
+
+        let mut by_hash: HashMap<[u8; 32], Vec<PathBuf>> = HashMap::new();
+        for candidate in candidates {
+            let bytes = fs::read(&candidate).map_err(|source| Error::ReadFile {
+                path: candidate.clone(),
+                source,
+            })?;
+            let hash = *blake3::hash(&bytes).as_bytes();
+            by_hash.entry(hash).or_default().push(candidate);
+        }
+
+        groups.extend(by_hash.into_values().filter(|group| group.len() > 1));
+    }
+
+    Ok(groups)
+}
+
+/// Whether `a` and `b` are already the same file on disk (hard-linked to
+/// one another), so relinking them would be a no-op at best.
+#[cfg(unix)]
+fn same_inode(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a_meta), Ok(b_meta)) => a_meta.dev() == b_meta.dev() && a_meta.ino() == b_meta.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_inode(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// Replace `duplicate` with a hard link to `kept`, without ever leaving
+/// `duplicate` deleted if the link cannot be created (e.g. cross-device
+/// `EXDEV`): the link is created under a temporary name first and only
+/// swapped into place once it succeeds. If `duplicate` can't be removed to
+/// make way for the swap, the temporary link is cleaned up too, so a failed
+/// dedupe never leaves `<name>.md-prune-dedupe-tmp` debris behind.
+fn hard_link_duplicate(kept: &Path, duplicate: &Path) -> std::io::Result<()> {
+    let tmp_name = format!(
+        "{}.md-prune-dedupe-tmp",
+        duplicate.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let tmp_path = duplicate.with_file_name(tmp_name);
+
+    fs::hard_link(kept, &tmp_path)?;
+    if let Err(source) = fs::remove_file(duplicate) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(source);
+    }
+    fs::rename(&tmp_path, duplicate)
+}
+
 pub(crate) fn generate_unique_filename(path: &Path) -> PathBuf {
     let parent = path.parent().unwrap();
     let stem = path.file_stem().unwrap().to_string_lossy();
@@ -82,3 +200,97 @@ pub(crate) fn generate_unique_filename(path: &Path) -> PathBuf {
         counter += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn actions_temp_dir(name: &str) -> PathBuf {
+        temp_dir("actions", name)
+    }
+
+    #[test]
+    fn group_duplicates_groups_by_content_not_just_size() {
+        let dir = actions_temp_dir("group-by-content");
+        let a = dir.join("a.png");
+        let b = dir.join("b.png");
+        let c = dir.join("c.png");
+        fs::write(&a, b"hello").unwrap();
+        fs::write(&b, b"hello").unwrap();
+        fs::write(&c, b"world").unwrap(); // same size as a/b, different content
+
+        let mut groups = group_duplicates(&[a.clone(), b.clone(), c.clone()]).unwrap();
+        assert_eq!(groups.len(), 1);
+
+        let mut group = groups.remove(0);
+        group.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(group, expected);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hard_link_duplicate_links_without_leaving_tmp_debris() {
+        let dir = actions_temp_dir("hard-link");
+        let kept = dir.join("kept.png");
+        let duplicate = dir.join("duplicate.png");
+        fs::write(&kept, b"same bytes").unwrap();
+        fs::write(&duplicate, b"same bytes").unwrap();
+
+        hard_link_duplicate(&kept, &duplicate).unwrap();
+
+        assert!(same_inode(&kept, &duplicate));
+        assert!(!dir.join("duplicate.png.md-prune-dedupe-tmp").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_action_dedupe_skips_already_hard_linked_duplicates() {
+        let dir = actions_temp_dir("dedupe-idempotent");
+        let kept = dir.join("kept.png");
+        let duplicate = dir.join("duplicate.png");
+        fs::write(&kept, b"same bytes").unwrap();
+        fs::hard_link(&kept, &duplicate).unwrap();
+
+        execute_action(
+            &Action::Dedupe,
+            &[kept.clone(), duplicate.clone()],
+            &dir,
+        )
+        .unwrap();
+
+        // Already hard-linked, so dedupe leaves it alone; the pair stays
+        // linked and the call succeeds.
+        assert!(same_inode(&kept, &duplicate));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_action_dedupe_replaces_distinct_duplicate_with_hard_link() {
+        let dir = actions_temp_dir("dedupe-real");
+        let kept = dir.join("kept.png");
+        let duplicate = dir.join("duplicate.png");
+        fs::write(&kept, b"same bytes").unwrap();
+        fs::write(&duplicate, b"same bytes").unwrap();
+
+        execute_action(
+            &Action::Dedupe,
+            &[kept.clone(), duplicate.clone()],
+            &dir,
+        )
+        .unwrap();
+
+        assert!(same_inode(&kept, &duplicate));
+        assert_eq!(fs::read(&duplicate).unwrap(), b"same bytes");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,15 @@
 use clap::Parser;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
+/// Camera RAW extension preset, expanded when `raw` appears in `--extensions`.
+const RAW_EXTENSIONS: &[&str] = &[
+    "nef", "cr2", "arw", "dng", "orf", "rw2", "raf", "pef", "srw",
+];
+
+/// HEIF/HEIC extension preset, expanded when `heif` appears in `--extensions`.
+const HEIF_EXTENSIONS: &[&str] = &["heic", "heif"];
+
 #[derive(Parser, Debug)]
 #[command(name = "md-prune-image")]
 #[command(about = "Remove orphaned image files from markdown directories", long_about = None)]
@@ -18,8 +27,33 @@ pub struct Cli {
     #[arg(long, group = "action", value_name = "DIR")]
     pub r#move: Option<PathBuf>,
 
+    /// Report orphan and missing image references instead of modifying anything
+    #[arg(long, group = "action")]
+    pub report: bool,
+
+    /// Replace byte-identical orphaned images with hard links to reclaim space
+    #[arg(long, group = "action")]
+    pub dedupe: bool,
+
+    /// Image file extensions to consider (comma-separated, also accepts the
+    /// presets `raw` and `heif`)
     #[arg(long, default_value = "jpg,jpeg,png,gif,bmp,svg,webp")]
     pub extensions: String,
+
+    /// Number of threads to use for scanning (defaults to all available CPU cores)
+    #[arg(long, value_name = "N")]
+    pub threads: Option<usize>,
+
+    /// Glob pattern to exclude from scanning, relative to DIRECTORY (repeatable).
+    /// A pattern with no "/" matches at any depth (e.g. "node_modules" also
+    /// prunes "docs/node_modules"); a pattern containing "/" is anchored to
+    /// DIRECTORY.
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Extensions that should never be treated as prunable images (comma-separated)
+    #[arg(long, value_name = "EXT", value_delimiter = ',')]
+    pub exclude_ext: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,11 +61,17 @@ pub enum Action {
     Delete,
     Recycle,
     Move(PathBuf),
+    Report,
+    Dedupe,
 }
 
 impl Cli {
     pub fn action(&self) -> Action {
-        if self.delete {
+        if self.report {
+            Action::Report
+        } else if self.dedupe {
+            Action::Dedupe
+        } else if self.delete {
             Action::Delete
         } else if let Some(ref dir) = self.r#move {
             Action::Move(dir.clone())
@@ -39,4 +79,80 @@ impl Cli {
             Action::Recycle
         }
     }
+
+    /// Resolve `--extensions` into a concrete set of lowercase extensions,
+    /// expanding any known presets (`raw`, `heif`) and dropping anything
+    /// named in `--exclude-ext`.
+    pub fn image_extensions(&self) -> HashSet<String> {
+        let mut extensions = HashSet::new();
+        for token in self.extensions.split(',') {
+            let token = token.trim().trim_start_matches('.').to_lowercase();
+            match token.as_str() {
+                "" => {}
+                "raw" => extensions.extend(RAW_EXTENSIONS.iter().map(|ext| ext.to_string())),
+                "heif" => extensions.extend(HEIF_EXTENSIONS.iter().map(|ext| ext.to_string())),
+                other => {
+                    extensions.insert(other.to_string());
+                }
+            }
+        }
+
+        for excluded in &self.exclude_ext {
+            let excluded = excluded.trim().trim_start_matches('.').to_lowercase();
+            extensions.remove(&excluded);
+        }
+
+        extensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cli(extensions: &str, exclude_ext: Vec<&str>) -> Cli {
+        Cli {
+            directory: PathBuf::from("."),
+            recycle: false,
+            delete: false,
+            r#move: None,
+            report: false,
+            dedupe: false,
+            extensions: extensions.to_string(),
+            threads: None,
+            exclude: Vec::new(),
+            exclude_ext: exclude_ext.into_iter().map(String::from).collect(),
+        }
+    }
+
+    #[test]
+    fn image_extensions_expands_raw_and_heif_presets() {
+        let extensions = cli("png,raw,heif", vec![]).image_extensions();
+
+        assert!(extensions.contains("png"));
+        for ext in RAW_EXTENSIONS {
+            assert!(extensions.contains(*ext));
+        }
+        for ext in HEIF_EXTENSIONS {
+            assert!(extensions.contains(*ext));
+        }
+    }
+
+    #[test]
+    fn image_extensions_drops_excluded_extensions_even_from_a_preset() {
+        let extensions = cli("png,raw", vec!["cr2", "PNG"]).image_extensions();
+
+        assert!(!extensions.contains("png"));
+        assert!(!extensions.contains("cr2"));
+        assert!(extensions.contains("nef"));
+    }
+
+    #[test]
+    fn image_extensions_normalizes_case_and_leading_dots() {
+        let extensions = cli(".JPG, .Png", vec![]).image_extensions();
+
+        assert_eq!(extensions.len(), 2);
+        assert!(extensions.contains("jpg"));
+        assert!(extensions.contains("png"));
+    }
 }
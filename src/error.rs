@@ -66,6 +66,14 @@ pub enum Error {
     /// Invalid regex pattern.
     #[error("invalid regex pattern")]
     InvalidRegex(#[from] regex::Error),
+
+    /// Invalid `--exclude` glob pattern.
+    #[error("invalid exclude glob pattern: {pattern}")]
+    InvalidGlob {
+        pattern: String,
+        #[source]
+        source: globset::Error,
+    },
 }
 
 /// Convenience Result type alias.
@@ -3,10 +3,13 @@ pub mod cli;
 pub mod error;
 pub mod parser;
 pub mod scanner;
+#[cfg(test)]
+mod test_support;
 pub mod utils;
 
 pub use actions::execute_action;
 pub use cli::{Action, Cli};
 pub use error::{Error, Result};
-pub use scanner::scan_for_orphans;
-pub use utils::display_relative_path;
+pub use parser::MissingReference;
+pub use scanner::{scan, scan_for_orphans, ScanReport};
+pub use utils::display_path_from_cwd;
@@ -1,18 +1,46 @@
 use crate::error::{Error, Result};
 use percent_encoding::percent_decode_str;
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub fn extract_image_references(markdown_path: &Path, base_dir: &Path) -> Result<HashSet<PathBuf>> {
+/// A markdown image reference that did not resolve to a file on disk.
+#[derive(Debug, Clone)]
+pub struct MissingReference {
+    /// The markdown file the reference was found in.
+    pub source: PathBuf,
+    /// 1-based line number of the reference within `source`.
+    pub line: usize,
+    /// The raw, unresolved link string as written in the markdown.
+    pub raw: String,
+}
+
+/// The image references found while parsing a single markdown file, split
+/// into paths that resolved to an existing file and ones that did not.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractedReferences {
+    pub resolved: HashSet<PathBuf>,
+    pub missing: Vec<MissingReference>,
+}
+
+pub fn extract_image_references(
+    markdown_path: &Path,
+    base_dir: &Path,
+) -> Result<ExtractedReferences> {
     let content = fs::read_to_string(markdown_path).map_err(|source| Error::ReadFile {
         path: markdown_path.to_path_buf(),
         source,
     })?;
 
-    let mut references = HashSet::new();
+    let mut references = ExtractedReferences::default();
     let markdown_dir = markdown_path.parent().unwrap_or(base_dir);
+    let ctx = ReferenceContext {
+        content: &content,
+        markdown_path,
+        markdown_dir,
+        base_dir,
+    };
 
     // Regex for markdown image syntax: ![alt](path) and ![alt](path "title")
     let img_pattern = Regex::new(r#"!\[.*?]\(([^)]+?)(?:\s+["'].*?["'])?\)"#)?;
@@ -20,30 +48,72 @@ pub fn extract_image_references(markdown_path: &Path, base_dir: &Path) -> Result
     // Regex for HTML img tags: <img src="path">
     let html_pattern = Regex::new(r#"<img[^>]+src=["']([^"']+)["']"#)?;
 
-    for cap in img_pattern.captures_iter(&content) {
-        if let Some(path_match) = cap.get(1) {
-            let img_path = path_match.as_str().trim();
+    // Obsidian/Foam-style wikilink embeds: ![[image.png]] or ![[image.png|200]]
+    let wikilink_pattern = Regex::new(r#"!\[\[([^\]|]+)(?:\|[^\]]*)?\]\]"#)?;
 
-            if is_url(img_path) {
-                continue;
-            }
+    // CommonMark reference-style images: ![alt][id]
+    let ref_style_pattern = Regex::new(r#"!\[.*?\]\[([^\]]+)\]"#)?;
 
-            if let Some(resolved) = resolve_image_path(img_path, markdown_dir, base_dir) {
-                references.insert(resolved);
-            }
+    // Reference definitions: [id]: path/to/image.png "title"
+    let ref_def_pattern = Regex::new(r#"(?m)^ {0,3}\[([^\]]+)\]:\s*(\S+)"#)?;
+
+    // Collect reference definitions first so `![alt][id]` can be resolved
+    // against them regardless of where in the file they're defined.
+    let mut ref_defs: HashMap<String, String> = HashMap::new();
+    for cap in ref_def_pattern.captures_iter(&content) {
+        if let (Some(id), Some(dest)) = (cap.get(1), cap.get(2)) {
+            ref_defs.insert(
+                id.as_str().trim().to_lowercase(),
+                dest.as_str()
+                    .trim_matches(|c| c == '<' || c == '>')
+                    .to_string(),
+            );
+        }
+    }
+
+    for cap in img_pattern.captures_iter(&content) {
+        if let Some(path_match) = cap.get(1) {
+            record_reference(
+                &mut references,
+                path_match.as_str().trim(),
+                path_match.start(),
+                &ctx,
+            );
         }
     }
 
     for cap in html_pattern.captures_iter(&content) {
         if let Some(path_match) = cap.get(1) {
-            let img_path = path_match.as_str().trim();
+            record_reference(
+                &mut references,
+                path_match.as_str().trim(),
+                path_match.start(),
+                &ctx,
+            );
+        }
+    }
 
-            if is_url(img_path) {
-                continue;
-            }
+    for cap in wikilink_pattern.captures_iter(&content) {
+        if let Some(path_match) = cap.get(1) {
+            record_reference(
+                &mut references,
+                path_match.as_str().trim(),
+                path_match.start(),
+                &ctx,
+            );
+        }
+    }
 
-            if let Some(resolved) = resolve_image_path(img_path, markdown_dir, base_dir) {
-                references.insert(resolved);
+    for cap in ref_style_pattern.captures_iter(&content) {
+        if let Some(id_match) = cap.get(1) {
+            let id = id_match.as_str().trim().to_lowercase();
+            match ref_defs.get(&id) {
+                Some(dest) => record_reference(&mut references, dest, id_match.start(), &ctx),
+                None => references.missing.push(MissingReference {
+                    source: markdown_path.to_path_buf(),
+                    line: line_number(&content, id_match.start()),
+                    raw: format!("[{}]", id_match.as_str().trim()),
+                }),
             }
         }
     }
@@ -51,6 +121,45 @@ pub fn extract_image_references(markdown_path: &Path, base_dir: &Path) -> Result
     Ok(references)
 }
 
+/// The per-file context needed to resolve and, if necessary, report a single
+/// image reference — bundled so `record_reference` doesn't have to take each
+/// piece as its own argument.
+struct ReferenceContext<'a> {
+    content: &'a str,
+    markdown_path: &'a Path,
+    markdown_dir: &'a Path,
+    base_dir: &'a Path,
+}
+
+fn record_reference(
+    references: &mut ExtractedReferences,
+    img_path: &str,
+    byte_offset: usize,
+    ctx: &ReferenceContext,
+) {
+    if is_url(img_path) {
+        return;
+    }
+
+    match resolve_image_path(img_path, ctx.markdown_dir, ctx.base_dir) {
+        Resolution::Resolved(resolved) => {
+            references.resolved.insert(resolved);
+        }
+        // Exists on disk, just outside the scan root (e.g. a real absolute
+        // path elsewhere) — not broken, so it's neither resolved nor missing.
+        Resolution::OutOfScope => {}
+        Resolution::Missing => references.missing.push(MissingReference {
+            source: ctx.markdown_path.to_path_buf(),
+            line: line_number(ctx.content, byte_offset),
+            raw: img_path.to_string(),
+        }),
+    }
+}
+
+fn line_number(content: &str, byte_offset: usize) -> usize {
+    content[..byte_offset].bytes().filter(|&b| b == b'\n').count() + 1
+}
+
 fn is_url(path: &str) -> bool {
     path.starts_with("http://")
         || path.starts_with("https://")
@@ -58,11 +167,19 @@ fn is_url(path: &str) -> bool {
         || path.starts_with("data:")
 }
 
-pub(crate) fn resolve_image_path(
-    img_path: &str,
-    markdown_dir: &Path,
-    base_dir: &Path,
-) -> Option<PathBuf> {
+/// The outcome of trying to resolve a reference to a file on disk.
+enum Resolution {
+    /// Resolved to a real file within the scan root.
+    Resolved(PathBuf),
+    /// Resolved to a real file, but outside the scan root (e.g. a genuine
+    /// absolute filesystem path pointing elsewhere) — not broken, just out
+    /// of scope for this scan.
+    OutOfScope,
+    /// No candidate path exists on disk.
+    Missing,
+}
+
+fn resolve_image_path(img_path: &str, markdown_dir: &Path, base_dir: &Path) -> Resolution {
     let decoded_path = percent_decode_str(img_path)
         .decode_utf8()
         .map(|s| s.into_owned())
@@ -74,42 +191,118 @@ pub(crate) fn resolve_image_path(
         .and_then(|s| s.split('?').next())
         .unwrap_or(&decoded_path);
 
-    try_resolve_path(clean_path, markdown_dir, base_dir).or_else(|| {
-        if clean_path != img_path {
+    match try_resolve_path(clean_path, markdown_dir, base_dir) {
+        Resolution::Missing if clean_path != img_path => {
             let clean_original = img_path
                 .split('#')
                 .next()
                 .and_then(|s| s.split('?').next())
                 .unwrap_or(img_path);
             try_resolve_path(clean_original, markdown_dir, base_dir)
-        } else {
-            None
         }
-    })
+        other => other,
+    }
 }
 
-fn try_resolve_path(img_path: &str, markdown_dir: &Path, base_dir: &Path) -> Option<PathBuf> {
-    let relative_to_md = markdown_dir.join(img_path);
-    if let Ok(canonical) = relative_to_md.canonicalize()
-        && canonical.starts_with(base_dir.canonicalize().ok()?)
+fn try_resolve_path(img_path: &str, markdown_dir: &Path, base_dir: &Path) -> Resolution {
+    let Ok(base_dir) = base_dir.canonicalize() else {
+        return Resolution::Missing;
+    };
+
+    // Site-root-absolute paths (e.g. "/assets/x.png", as emitted by Hugo,
+    // Jekyll, or MkDocs) are relative to the scan root, not the filesystem
+    // root, so try that resolution before treating the path as OS-absolute.
+    if let Some(site_relative) = img_path.strip_prefix('/')
+        && let Ok(canonical) = base_dir.join(site_relative).canonicalize()
     {
-        return Some(canonical);
+        return classify(canonical, &base_dir);
+    }
+
+    let relative_to_md = markdown_dir.join(img_path);
+    if let Ok(canonical) = relative_to_md.canonicalize() {
+        return classify(canonical, &base_dir);
     }
 
     let relative_to_base = base_dir.join(img_path);
-    if let Ok(canonical) = relative_to_base.canonicalize()
-        && canonical.starts_with(base_dir.canonicalize().ok()?)
-    {
-        return Some(canonical);
+    if let Ok(canonical) = relative_to_base.canonicalize() {
+        return classify(canonical, &base_dir);
     }
 
     let abs_path = PathBuf::from(img_path);
     if abs_path.is_absolute()
         && let Ok(canonical) = abs_path.canonicalize()
-        && canonical.starts_with(base_dir.canonicalize().ok()?)
     {
-        return Some(canonical);
+        return classify(canonical, &base_dir);
+    }
+
+    Resolution::Missing
+}
+
+fn classify(canonical: PathBuf, base_dir: &Path) -> Resolution {
+    if canonical.starts_with(base_dir) {
+        Resolution::Resolved(canonical)
+    } else {
+        Resolution::OutOfScope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn parser_temp_dir(name: &str) -> PathBuf {
+        temp_dir("parser", name)
+    }
+
+    #[test]
+    fn wikilink_with_and_without_size_resolves() {
+        let dir = parser_temp_dir("wikilink");
+        fs::write(dir.join("image.png"), b"data").unwrap();
+        let md = dir.join("note.md");
+        fs::write(&md, "![[image.png]]\n![[image.png|200]]\n").unwrap();
+
+        let refs = extract_image_references(&md, &dir).unwrap();
+
+        assert_eq!(refs.resolved.len(), 1);
+        assert!(refs.resolved.contains(&dir.join("image.png").canonicalize().unwrap()));
+        assert!(refs.missing.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reference_style_image_resolved_and_unresolved() {
+        let dir = parser_temp_dir("ref-style");
+        fs::write(dir.join("image.png"), b"data").unwrap();
+        let md = dir.join("note.md");
+        fs::write(
+            &md,
+            "![alt][img]\n![alt][missing-id]\n\n[img]: image.png\n",
+        )
+        .unwrap();
+
+        let refs = extract_image_references(&md, &dir).unwrap();
+
+        assert_eq!(refs.resolved.len(), 1);
+        assert_eq!(refs.missing.len(), 1);
+        assert_eq!(refs.missing[0].raw, "[missing-id]");
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    None
+    #[test]
+    fn reference_definition_reused_by_two_images_resolves_both() {
+        let dir = parser_temp_dir("ref-reuse");
+        fs::write(dir.join("image.png"), b"data").unwrap();
+        let md = dir.join("note.md");
+        fs::write(&md, "![first][img]\n![second][img]\n\n[img]: image.png\n").unwrap();
+
+        let refs = extract_image_references(&md, &dir).unwrap();
+
+        assert_eq!(refs.resolved.len(), 1);
+        assert!(refs.missing.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -1,18 +1,27 @@
 use crate::cli::Cli;
-use crate::error::Result;
-use crate::parser::extract_image_references;
+use crate::error::{Error, Result};
+use crate::parser::{extract_image_references, ExtractedReferences, MissingReference};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-pub fn scan_for_orphans(cli: &Cli) -> Result<Vec<PathBuf>> {
-    let image_extensions: HashSet<String> = cli
-        .extensions
-        .split(',')
-        .map(|s| s.trim().to_lowercase())
-        .collect();
+/// The full classification of a scan: images on disk with no reference
+/// (`orphan`), markdown references that resolve to nothing (`missing`), and
+/// images that are referenced and present (`ok`).
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub orphan: Vec<PathBuf>,
+    pub missing: Vec<MissingReference>,
+    pub ok: Vec<PathBuf>,
+}
 
-    let all_images = walk_files(&cli.directory)
+pub fn scan(cli: &Cli) -> Result<ScanReport> {
+    let exclude = build_exclude_matcher(&cli.exclude)?;
+    let image_extensions = cli.image_extensions();
+
+    let image_paths: Vec<PathBuf> = walk_files(&cli.directory, &exclude)
         .filter(|entry| {
             entry
                 .path()
@@ -20,14 +29,15 @@ pub fn scan_for_orphans(cli: &Cli) -> Result<Vec<PathBuf>> {
                 .map(|ext| image_extensions.contains(&ext.to_string_lossy().to_lowercase()))
                 .unwrap_or(false)
         })
-        .filter_map(|entry| entry.path().canonicalize().ok())
-        .collect::<HashSet<PathBuf>>();
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
 
-    let referenced_images: HashSet<PathBuf> = WalkDir::new(&cli.directory)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|entry| entry.file_type().is_file())
+    let all_images: HashSet<PathBuf> = image_paths
+        .par_iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+
+    let markdown_paths: Vec<PathBuf> = walk_files(&cli.directory, &exclude)
         .filter(|entry| {
             entry
                 .path()
@@ -35,19 +45,206 @@ pub fn scan_for_orphans(cli: &Cli) -> Result<Vec<PathBuf>> {
                 .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
                 .unwrap_or(false)
         })
-        .filter_map(|entry| extract_image_references(entry.path(), &cli.directory).ok())
-        .flatten()
+        .map(|entry| entry.path().to_path_buf())
         .collect();
 
-    let orphaned: Vec<PathBuf> = all_images.difference(&referenced_images).cloned().collect();
+    let extracted: ExtractedReferences = markdown_paths
+        .par_iter()
+        .filter_map(|path| extract_image_references(path, &cli.directory).ok())
+        .reduce(ExtractedReferences::default, |mut acc, refs| {
+            acc.resolved.extend(refs.resolved);
+            acc.missing.extend(refs.missing);
+            acc
+        });
 
-    Ok(orphaned)
+    let orphan: Vec<PathBuf> = all_images
+        .difference(&extracted.resolved)
+        .cloned()
+        .collect();
+    let ok: Vec<PathBuf> = all_images
+        .intersection(&extracted.resolved)
+        .cloned()
+        .collect();
+
+    Ok(ScanReport {
+        orphan,
+        missing: extracted.missing,
+        ok,
+    })
+}
+
+pub fn scan_for_orphans(cli: &Cli) -> Result<Vec<PathBuf>> {
+    Ok(scan(cli)?.orphan)
 }
 
-pub(crate) fn walk_files(directory: &PathBuf) -> impl Iterator<Item = walkdir::DirEntry> {
+/// Compile the repeatable `--exclude` globs into a single matcher so
+/// `walk_files` can prune whole subtrees (e.g. `node_modules`, `.git`) in
+/// one pass instead of re-parsing patterns per entry.
+///
+/// A pattern with no path separator is anchored with a leading `**/` so it
+/// matches at any depth (e.g. `--exclude node_modules` also prunes a nested
+/// `docs/node_modules`), matching how gitignore treats unanchored patterns.
+/// A pattern that already contains a `/` is left as-is, anchored to the
+/// scan root.
+pub(crate) fn build_exclude_matcher(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let anchored = if pattern.contains('/') {
+            pattern.clone()
+        } else {
+            format!("**/{pattern}")
+        };
+
+        let glob = Glob::new(&anchored).map_err(|source| Error::InvalidGlob {
+            pattern: pattern.clone(),
+            source,
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|source| Error::InvalidGlob {
+        pattern: patterns.join(", "),
+        source,
+    })
+}
+
+pub(crate) fn walk_files<'a>(
+    directory: &'a Path,
+    exclude: &'a GlobSet,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
     WalkDir::new(directory)
         .follow_links(false)
         .into_iter()
+        .filter_entry(move |entry| {
+            let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+            !exclude.is_match(relative)
+        })
         .filter_map(|e| e.ok())
         .filter(|entry| entry.file_type().is_file())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use std::fs;
+
+    fn scanner_temp_dir(name: &str) -> PathBuf {
+        temp_dir("scanner", name)
+    }
+
+    fn cli_for(directory: PathBuf) -> Cli {
+        Cli {
+            directory,
+            recycle: false,
+            delete: false,
+            r#move: None,
+            report: true,
+            dedupe: false,
+            extensions: "png".to_string(),
+            threads: None,
+            exclude: Vec::new(),
+            exclude_ext: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn scan_classifies_orphan_missing_and_ok() {
+        let dir = scanner_temp_dir("classify");
+        fs::write(dir.join("used.png"), b"data").unwrap();
+        fs::write(dir.join("orphan.png"), b"data").unwrap();
+        fs::write(
+            dir.join("note.md"),
+            "![used](used.png)\n![gone](missing.png)\n",
+        )
+        .unwrap();
+
+        let report = scan(&cli_for(dir.clone())).unwrap();
+
+        assert_eq!(report.orphan.len(), 1);
+        assert!(report.orphan[0].ends_with("orphan.png"));
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].raw, "missing.png");
+        assert_eq!(report.ok.len(), 1);
+        assert!(report.ok[0].ends_with("used.png"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn scan_for_orphans_returns_only_the_orphan_subset() {
+        let dir = scanner_temp_dir("orphans-only");
+        fs::write(dir.join("used.png"), b"data").unwrap();
+        fs::write(dir.join("orphan.png"), b"data").unwrap();
+        fs::write(dir.join("note.md"), "![used](used.png)\n").unwrap();
+
+        let orphans = scan_for_orphans(&cli_for(dir.clone())).unwrap();
+
+        assert_eq!(orphans.len(), 1);
+        assert!(orphans[0].ends_with("orphan.png"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn scan_merges_results_across_many_markdown_files_in_parallel() {
+        let dir = scanner_temp_dir("parallel-merge");
+        for i in 0..20 {
+            fs::write(dir.join(format!("used{i}.png")), b"data").unwrap();
+            fs::write(
+                dir.join(format!("note{i}.md")),
+                format!("![used](used{i}.png)\n![gone](missing{i}.png)\n"),
+            )
+            .unwrap();
+        }
+        fs::write(dir.join("orphan.png"), b"data").unwrap();
+
+        let report = scan(&cli_for(dir.clone())).unwrap();
+
+        assert_eq!(report.ok.len(), 20);
+        assert_eq!(report.missing.len(), 20);
+        assert_eq!(report.orphan.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exclude_without_slash_prunes_at_any_depth() {
+        let dir = scanner_temp_dir("exclude-any-depth");
+        fs::create_dir_all(dir.join("node_modules")).unwrap();
+        fs::create_dir_all(dir.join("docs/node_modules")).unwrap();
+        fs::write(dir.join("node_modules/a.png"), b"data").unwrap();
+        fs::write(dir.join("docs/node_modules/b.png"), b"data").unwrap();
+        fs::write(dir.join("docs/keep.png"), b"data").unwrap();
+
+        let exclude = build_exclude_matcher(&["node_modules".to_string()]).unwrap();
+        let files: Vec<PathBuf> = walk_files(&dir, &exclude)
+            .map(|entry| entry.path().to_path_buf())
+            .collect();
+
+        assert!(files.iter().any(|p| p.ends_with("docs/keep.png")));
+        assert!(!files
+            .iter()
+            .any(|p| p.to_string_lossy().contains("node_modules")));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn exclude_with_slash_only_anchors_at_the_scan_root() {
+        let dir = scanner_temp_dir("exclude-anchored");
+        fs::create_dir_all(dir.join("assets")).unwrap();
+        fs::create_dir_all(dir.join("docs/assets")).unwrap();
+        fs::write(dir.join("assets/root.png"), b"data").unwrap();
+        fs::write(dir.join("docs/assets/nested.png"), b"data").unwrap();
+
+        let exclude = build_exclude_matcher(&["assets/*".to_string()]).unwrap();
+        let files: Vec<PathBuf> = walk_files(&dir, &exclude)
+            .map(|entry| entry.path().to_path_buf())
+            .collect();
+
+        assert!(!files.iter().any(|p| p.ends_with("assets/root.png")));
+        assert!(files.iter().any(|p| p.ends_with("docs/assets/nested.png")));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
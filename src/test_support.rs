@@ -0,0 +1,18 @@
+//! Fixtures shared by the `mod tests` blocks scattered across the crate, so
+//! each one doesn't have to redefine its own scratch-directory helper.
+#![cfg(test)]
+
+use std::fs;
+use std::path::PathBuf;
+
+/// A fresh scratch directory under the OS temp dir, unique per test and
+/// process so parallel test runs don't collide.
+pub(crate) fn temp_dir(module: &str, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "md-prune-image-test-{module}-{name}-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -1,7 +1,75 @@
 use std::path::Path;
 
-pub fn display_relative_path(path: &Path, base_dir: &Path) -> String {
-    path.strip_prefix(base_dir)
+/// Render `path` relative to the current working directory when possible,
+/// falling back to the absolute path if it lies outside CWD. Paths printed
+/// this way can be pasted directly into a follow-up shell command,
+/// regardless of where the scan directory was relative to where the tool
+/// was invoked.
+pub fn display_path_from_cwd(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(&cwd).ok())
         .map(|p| p.display().to_string().replace('\\', "/"))
-        .unwrap_or_else(|_| path.display().to_string().replace('\\', "/"))
+        .unwrap_or_else(|| path.display().to_string().replace('\\', "/"))
+}
+
+/// Format a byte count as a human-readable size (e.g. "1.50 MB").
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{size:.2} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn display_path_from_cwd_strips_the_cwd_prefix() {
+        let cwd = std::env::current_dir().unwrap();
+        let inside = cwd.join("some/nested/file.png");
+
+        assert_eq!(display_path_from_cwd(&inside), "some/nested/file.png");
+    }
+
+    #[test]
+    fn display_path_from_cwd_falls_back_to_the_absolute_path_outside_cwd() {
+        let outside = PathBuf::from("/definitely/outside/the/cwd/file.png");
+
+        assert_eq!(
+            display_path_from_cwd(&outside),
+            "/definitely/outside/the/cwd/file.png"
+        );
+    }
+
+    #[test]
+    fn format_bytes_stays_in_bytes_below_a_kilobyte() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+    }
+
+    #[test]
+    fn format_bytes_picks_the_largest_unit_that_keeps_the_value_above_one() {
+        assert_eq!(format_bytes(1024), "1.00 KB");
+        assert_eq!(format_bytes(1536), "1.50 KB");
+        assert_eq!(format_bytes(1024 * 1024), "1.00 MB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.00 GB");
+    }
+
+    #[test]
+    fn format_bytes_caps_at_terabytes_instead_of_overflowing_the_unit_table() {
+        assert_eq!(format_bytes(1024u64.pow(5)), "1024.00 TB");
+    }
 }